@@ -0,0 +1,123 @@
+use std::{collections::HashMap, env, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+use regex::Regex;
+use tokio::fs;
+
+use crate::cli::AllowCommand;
+
+fn allowlist_path() -> Result<PathBuf> {
+    let home = env::var_os("HOME").context("missing HOME environment variable")?;
+
+    let mut path = PathBuf::from(&home);
+    path.extend([".local", "state", "maintenance", "journalctl_allow.json"]);
+
+    Ok(path)
+}
+
+fn new_log_path() -> Result<PathBuf> {
+    let home = env::var_os("HOME").context("missing HOME environment variable")?;
+
+    let mut path = PathBuf::from(&home);
+    path.extend([".local", "state", "maintenance", "journalctl_new.log"]);
+
+    Ok(path)
+}
+
+async fn load() -> Result<HashMap<String, Vec<String>>> {
+    let path = allowlist_path()?;
+
+    match fs::read_to_string(&path).await {
+        Ok(contents) => serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+async fn save(allowlist: &HashMap<String, Vec<String>>) -> Result<()> {
+    let path = allowlist_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+
+    let contents =
+        serde_json::to_string_pretty(allowlist).context("failed to serialize allowlist")?;
+
+    fs::write(&path, contents)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Handles the `maintenance allow` subcommands.
+pub async fn dispatch(command: AllowCommand) -> Result<()> {
+    match command {
+        AllowCommand::Add { identifier, regex } => add(&identifier, &regex).await,
+        AllowCommand::Remove { identifier, regex } => remove(&identifier, &regex).await,
+        AllowCommand::List => list().await,
+        AllowCommand::Suggest => suggest().await,
+    }
+}
+
+async fn add(identifier: &str, regex: &str) -> Result<()> {
+    Regex::new(regex).with_context(|| format!("'{regex}' is not a valid regex"))?;
+
+    let mut allowlist = load().await?;
+    let regexes = allowlist.entry(identifier.to_string()).or_default();
+    if !regexes.iter().any(|existing| existing == regex) {
+        regexes.push(regex.to_string());
+    }
+
+    save(&allowlist).await
+}
+
+async fn remove(identifier: &str, regex: &str) -> Result<()> {
+    let mut allowlist = load().await?;
+
+    if let Some(regexes) = allowlist.get_mut(identifier) {
+        regexes.retain(|existing| existing != regex);
+        if regexes.is_empty() {
+            allowlist.remove(identifier);
+        }
+    }
+
+    save(&allowlist).await
+}
+
+async fn list() -> Result<()> {
+    let allowlist = load().await?;
+
+    let mut identifiers = allowlist.keys().collect::<Vec<_>>();
+    identifiers.sort();
+
+    for identifier in identifiers {
+        for regex in &allowlist[identifier] {
+            println!("{identifier}\t{regex}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn suggest() -> Result<()> {
+    let path = new_log_path()?;
+    let contents = fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    for line in contents.lines() {
+        let Some((identifier, message)) = line.split_once(": ") else {
+            continue;
+        };
+
+        println!(
+            "maintenance allow add {identifier} '{}'",
+            regex::escape(message)
+        );
+    }
+
+    Ok(())
+}
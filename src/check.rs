@@ -0,0 +1,57 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::{db::Db, notifier::Urgency};
+
+/// Context shared by every check, e.g. the dedup state database.
+pub(crate) struct CheckContext<'a> {
+    pub(crate) db: &'a Db,
+}
+
+/// One thing a `Check` wants to tell the user about, plus what to do if
+/// they invoke the notification's default action.
+pub(crate) struct Finding {
+    pub(crate) summary: String,
+    pub(crate) body: String,
+    pub(crate) urgency: Urgency,
+    pub(crate) action_label: &'static str,
+    pub(crate) action_command: Option<(&'static str, Vec<String>)>,
+    /// The icon to pass through to notifier backends that can show one,
+    /// normally the producing `Check`'s `icon()`.
+    pub(crate) icon: &'static str,
+    /// Persists the dedup state backing this finding as notified. Called
+    /// only once the dispatcher confirms a notifier actually delivered
+    /// it, so a finding no backend could deliver keeps nagging instead of
+    /// being silently marked as seen.
+    pub(crate) mark_notified: Option<Box<dyn FnOnce(&Db) -> Result<()> + Send>>,
+}
+
+/// Something that can be checked for problems worth notifying about.
+#[async_trait]
+pub(crate) trait Check: Send + Sync {
+    /// Short identifier used on the command line (`maintenance run --check <name>`).
+    fn name(&self) -> &'static str;
+
+    /// Icon name passed through to notifier backends that can display
+    /// one (currently just the D-Bus backend; others ignore it).
+    fn icon(&self) -> &'static str;
+
+    async fn run(&self, ctx: &CheckContext<'_>) -> Result<Vec<Finding>>;
+}
+
+/// The set of checks `maintenance` knows how to run.
+pub(crate) struct Checks(Vec<Box<dyn Check>>);
+
+impl Checks {
+    pub(crate) fn all() -> Self {
+        Self(vec![
+            Box::new(crate::checks::systemctl::SystemctlCheck),
+            Box::new(crate::checks::journal::JournalCheck),
+            Box::new(crate::checks::updates::UpdatesCheck),
+        ])
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &dyn Check> {
+        self.0.iter().map(AsRef::as_ref)
+    }
+}
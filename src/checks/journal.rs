@@ -0,0 +1,176 @@
+use core::fmt::{self, Write as _};
+use std::{collections::HashMap, env, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use log::{error, info};
+use regex::RegexSet;
+use serde::{Deserialize, Deserializer, de};
+use tokio::{fs, process::Command};
+
+use crate::{
+    check::{Check, CheckContext, Finding},
+    notifier::Urgency,
+};
+
+pub(crate) struct JournalctlAllow {
+    matcher: RegexSet,
+}
+
+impl JournalctlAllow {
+    pub(crate) fn is_match(&self, message: &str) -> bool {
+        self.matcher.is_match(message)
+    }
+}
+
+impl<'de> Deserialize<'de> for JournalctlAllow {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BuildMatcher;
+
+        impl<'de> de::Visitor<'de> for BuildMatcher {
+            type Value = RegexSet;
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut matchers = Vec::new();
+
+                while let Some(regex) = seq.next_element::<String>()? {
+                    matchers.push(format!("^{regex}$"));
+                }
+
+                RegexSet::new(matchers.iter())
+                    .map_err(|_| de::Error::custom("failed to build regex matchers"))
+            }
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a set of regexes")
+            }
+        }
+
+        Ok(Self {
+            matcher: deserializer.deserialize_any(BuildMatcher)?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct JournalctlEntry {
+    #[serde(rename(deserialize = "SYSLOG_IDENTIFIER"))]
+    pub(crate) identifier: String,
+    #[serde(rename(deserialize = "MESSAGE"))]
+    pub(crate) message: Option<String>,
+}
+
+/// Reads and parses `~/.local/state/maintenance/journalctl_allow.json`.
+pub(crate) async fn load_journalctl_allowlist() -> Result<HashMap<String, JournalctlAllow>> {
+    let home = env::var_os("HOME").context("missing HOME environment variable")?;
+
+    let mut allowlist_path = PathBuf::from(&home);
+    allowlist_path.extend([".local", "state", "maintenance", "journalctl_allow.json"]);
+
+    let allowlist = fs::read_to_string(&allowlist_path)
+        .await
+        .context("failed to read allowlist from journalctl_allow.json")?;
+
+    serde_json::from_str::<HashMap<String, JournalctlAllow>>(&allowlist)
+        .context("failed to deserialize allowlist from journalctl_allow.json")
+}
+
+pub(crate) struct JournalCheck;
+
+#[async_trait]
+impl Check for JournalCheck {
+    fn name(&self) -> &'static str {
+        "journal"
+    }
+
+    fn icon(&self) -> &'static str {
+        "dialog-warning-symbolic"
+    }
+
+    async fn run(&self, ctx: &CheckContext<'_>) -> Result<Vec<Finding>> {
+        info!("checking for journalctl errors from boot");
+
+        let home = env::var_os("HOME").context("missing HOME environment variable")?;
+
+        let allowlist = load_journalctl_allowlist().await?;
+
+        let output = Command::new("/usr/bin/journalctl")
+            .args(["--boot", "--priority=err", "--output=json"])
+            .output()
+            .await
+            .context("failed to run journalctl")?
+            .stdout;
+        let errors = serde_json::Deserializer::from_str(
+            str::from_utf8(&output).context("journalctl output was invalid UTF-8")?,
+        )
+        .into_iter::<JournalctlEntry>();
+
+        let mut error_log_contents = String::new();
+        let mut unmatched_count = 0;
+        let mut new_entries = Vec::new();
+        for error in errors {
+            let error = error.context("journalctl produced invalid JSON")?;
+            if let Some(message) = &error.message
+                && let Some(allow) = allowlist.get(&error.identifier)
+                && allow.is_match(message)
+            {
+                continue;
+            }
+
+            unmatched_count += 1;
+
+            let message = error.message.as_deref().unwrap_or("");
+            if ctx
+                .db
+                .observe_journalctl_error(&error.identifier, message)
+                .inspect_err(|e| {
+                    error!(
+                        "failed to record journalctl error from '{}': {e:?}",
+                        error.identifier
+                    )
+                })
+                .unwrap_or(false)
+            {
+                new_entries.push((error.identifier.clone(), message.to_string()));
+            }
+
+            writeln!(&mut error_log_contents, "{}: {}", error.identifier, message)?;
+        }
+
+        let mut error_log_path = PathBuf::from(&home);
+        error_log_path.extend([".local", "state", "maintenance", "journalctl_new.log"]);
+
+        fs::write(error_log_path, error_log_contents)
+            .await
+            .context("failed to create journalctl log")?;
+
+        if unmatched_count == 0 || new_entries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let summary = "Unrecognized errors in journalctl".to_string();
+        let body = if new_entries.len() == 1 {
+            "1 error not found in allowlist.".to_string()
+        } else {
+            format!("{} errors not found in allowlist.", new_entries.len())
+        };
+
+        Ok(vec![Finding {
+            summary,
+            body,
+            urgency: Urgency::Critical,
+            action_label: "View Errors",
+            action_command: Some(("/usr/bin/xdg-open", vec!["journalctl_new.log".to_string()])),
+            icon: self.icon(),
+            mark_notified: Some(Box::new(move |db| {
+                db.mark_journalctl_errors_notified(&new_entries)
+            })),
+        }])
+    }
+}
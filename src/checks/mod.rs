@@ -0,0 +1,3 @@
+pub(crate) mod journal;
+pub(crate) mod systemctl;
+pub(crate) mod updates;
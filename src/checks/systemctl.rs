@@ -0,0 +1,91 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use log::{error, info};
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::{
+    check::{Check, CheckContext, Finding},
+    notifier::Urgency,
+};
+
+#[derive(Deserialize)]
+struct SystemctlUnit {
+    unit: String,
+    description: String,
+}
+
+pub(crate) struct SystemctlCheck;
+
+#[async_trait]
+impl Check for SystemctlCheck {
+    fn name(&self) -> &'static str {
+        "systemctl"
+    }
+
+    fn icon(&self) -> &'static str {
+        "dialog-warning-symbolic"
+    }
+
+    async fn run(&self, ctx: &CheckContext<'_>) -> Result<Vec<Finding>> {
+        info!("checking for systemctl failures");
+
+        let output = Command::new("/usr/bin/systemctl")
+            .args(["--failed", "--output=json"])
+            .output()
+            .await
+            .context("failed to run systemctl")?
+            .stdout;
+        let failed = serde_json::from_slice::<Vec<SystemctlUnit>>(&output)
+            .context("failed to parse systemctl output as json")?;
+
+        ctx.db
+            .forget_failed_units_except(
+                &failed.iter().map(|unit| unit.unit.clone()).collect::<Vec<_>>(),
+            )
+            .context("failed to prune failed units from state database")?;
+
+        let new_failed = failed
+            .iter()
+            .filter(|unit| {
+                ctx.db
+                    .observe_failed_unit(&unit.unit)
+                    .inspect_err(|e| error!("failed to record failed unit '{}': {e:?}", unit.unit))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+
+        if new_failed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let units = new_failed
+            .iter()
+            .map(|unit| unit.unit.clone())
+            .collect::<Vec<_>>();
+
+        let summary;
+        let body;
+
+        if new_failed.len() == 1 {
+            summary = "Systemd unit failed to load".to_string();
+            body = format!(
+                "'{}' ({}) failed to start normally.",
+                new_failed[0].description, new_failed[0].unit
+            );
+        } else {
+            summary = "Multiple systemd units failed to load".to_string();
+            body = format!("{} units failed to start normally.", new_failed.len());
+        }
+
+        Ok(vec![Finding {
+            summary,
+            body,
+            urgency: Urgency::Critical,
+            action_label: "Investigate",
+            action_command: Some(("/usr/bin/kgx", vec!["--command=systemctl --failed".to_string()])),
+            icon: self.icon(),
+            mark_notified: Some(Box::new(move |db| db.mark_failed_units_notified(&units))),
+        }])
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use log::{error, info};
+use tokio::process::Command;
+
+use crate::{
+    check::{Check, CheckContext, Finding},
+    notifier::Urgency,
+};
+
+pub(crate) struct UpdatesCheck;
+
+#[async_trait]
+impl Check for UpdatesCheck {
+    fn name(&self) -> &'static str {
+        "updates"
+    }
+
+    fn icon(&self) -> &'static str {
+        "software-update-available"
+    }
+
+    async fn run(&self, ctx: &CheckContext<'_>) -> Result<Vec<Finding>> {
+        info!("checking for package updates");
+
+        let output = Command::new("/usr/bin/checkupdates")
+            .output()
+            .await
+            .context("failed to run checkupdates")?
+            .stdout;
+        let updates = str::from_utf8(&output)
+            .context("checkupdates output was not UTF-8")?
+            .trim_end();
+
+        let packages = updates
+            .lines()
+            .map(|line| line.split_once(' ').unwrap_or((line, "")).0.to_string())
+            .collect::<Vec<_>>();
+
+        ctx.db
+            .forget_pending_updates_except(&packages)
+            .context("failed to prune pending updates from state database")?;
+
+        if packages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let new_packages = packages
+            .iter()
+            .filter(|package| {
+                ctx.db
+                    .observe_pending_update(package)
+                    .inspect_err(|e| error!("failed to record pending update '{package}': {e:?}"))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+
+        if new_packages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let notified_packages = new_packages.iter().map(|package| (*package).clone()).collect::<Vec<_>>();
+        let count = new_packages.len();
+        let summary = "Updates available".to_string();
+        let body = if count == 1 {
+            format!("'{}' is ready to update.", new_packages[0])
+        } else {
+            format!("{count} packages are ready to update.")
+        };
+
+        Ok(vec![Finding {
+            summary,
+            body,
+            urgency: Urgency::Critical,
+            action_label: "Update",
+            action_command: Some(("/usr/bin/kgx", vec!["--command=sudo pacman -Syu".to_string()])),
+            icon: self.icon(),
+            mark_notified: Some(Box::new(move |db| {
+                db.mark_pending_updates_notified(&notified_packages)
+            })),
+        }])
+    }
+}
@@ -0,0 +1,69 @@
+use clap::{Parser, Subcommand, ValueEnum};
+
+/// Runs maintenance checks and manages the journalctl allowlist.
+#[derive(Parser)]
+#[command(name = "maintenance", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run maintenance checks.
+    Run {
+        /// Run only this check instead of all of them.
+        #[arg(long)]
+        check: Option<CheckKind>,
+    },
+    /// Manage the journalctl allowlist.
+    Allow {
+        #[command(subcommand)]
+        command: AllowCommand,
+    },
+    /// Run checks continuously instead of once.
+    Daemon {
+        /// Seconds between systemctl checks.
+        #[arg(long, default_value_t = 3600)]
+        systemctl_period: u64,
+        /// Seconds between update checks.
+        #[arg(long, default_value_t = 21_600)]
+        updates_period: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum CheckKind {
+    Systemctl,
+    Journal,
+    Updates,
+}
+
+impl CheckKind {
+    /// The corresponding `Check::name()` in the check registry.
+    pub fn as_name(self) -> &'static str {
+        match self {
+            Self::Systemctl => "systemctl",
+            Self::Journal => "journal",
+            Self::Updates => "updates",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum AllowCommand {
+    /// Allow a regex to match messages from an identifier.
+    Add {
+        identifier: String,
+        regex: String,
+    },
+    /// Stop allowing a regex for an identifier.
+    Remove {
+        identifier: String,
+        regex: String,
+    },
+    /// Print the current allowlist.
+    List,
+    /// Suggest allowlist entries from the last journalctl_new.log.
+    Suggest,
+}
@@ -0,0 +1,85 @@
+use std::{env, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+use serde::Deserialize;
+use zbus::Connection;
+
+use crate::notifier::{Notifier, matrix::Matrix, smtp::Smtp, webhook::Webhook};
+use crate::notifications::Notifications;
+
+/// One entry in `~/.config/maintenance/notifiers.toml`, in the order
+/// notifications should fan out to them.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum NotifierEntry {
+    Dbus,
+    Smtp {
+        host: String,
+        port: u16,
+        username: String,
+        password: String,
+        from: String,
+        to: String,
+    },
+    Webhook {
+        url: String,
+    },
+    Matrix {
+        homeserver: String,
+        access_token: String,
+        room_id: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct NotifiersConfig {
+    #[serde(default)]
+    notifier: Vec<NotifierEntry>,
+}
+
+/// Reads `~/.config/maintenance/notifiers.toml` and builds the ordered
+/// list of notifier backends it describes. If the file doesn't exist,
+/// falls back to the D-Bus desktop notifier alone.
+pub async fn load_notifiers(connection: &Connection) -> Result<Vec<Box<dyn Notifier>>> {
+    let home = env::var_os("HOME").context("missing HOME environment variable")?;
+
+    let mut path = PathBuf::from(&home);
+    path.extend([".config", "maintenance", "notifiers.toml"]);
+
+    let entries = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => {
+            toml::from_str::<NotifiersConfig>(&contents)
+                .context("failed to parse notifiers.toml")?
+                .notifier
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![NotifierEntry::Dbus],
+        Err(e) => return Err(e).context("failed to read notifiers.toml"),
+    };
+
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::with_capacity(entries.len());
+    for entry in entries {
+        notifiers.push(match entry {
+            NotifierEntry::Dbus => Box::new(
+                Notifications::start(connection)
+                    .await
+                    .context("failed to start D-Bus notifier")?,
+            ) as Box<dyn Notifier>,
+            NotifierEntry::Smtp {
+                host,
+                port,
+                username,
+                password,
+                from,
+                to,
+            } => Box::new(Smtp::new(&host, port, &username, &password, &from, &to)?) as Box<dyn Notifier>,
+            NotifierEntry::Webhook { url } => Box::new(Webhook::new(&url)) as Box<dyn Notifier>,
+            NotifierEntry::Matrix {
+                homeserver,
+                access_token,
+                room_id,
+            } => Box::new(Matrix::new(&homeserver, &access_token, &room_id)) as Box<dyn Notifier>,
+        });
+    }
+
+    Ok(notifiers)
+}
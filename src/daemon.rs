@@ -0,0 +1,166 @@
+use std::{collections::HashMap, process::Stdio, time::Duration};
+
+use anyhow::{Context as _, Result};
+use dbus_tokio::connection;
+use log::{error, info};
+use tokio::{
+    io::{AsyncBufReadExt as _, BufReader, Lines},
+    process::{ChildStdout, Command},
+    time,
+};
+
+use crate::{
+    check::{Check, CheckContext, Finding},
+    checks::journal::{JournalCheck, JournalctlAllow, JournalctlEntry, load_journalctl_allowlist},
+    checks::systemctl::SystemctlCheck,
+    checks::updates::UpdatesCheck,
+    config,
+    db::Db,
+    dispatch_finding,
+    events::EventBus,
+    notifier::Urgency,
+};
+
+/// Keeps the D-Bus connection and notifier backends alive indefinitely,
+/// driving `systemctl`/`updates` checks off timers and following the
+/// journal live for errors instead of re-scanning the whole boot log.
+pub(crate) async fn run(systemctl_period: u64, updates_period: u64) -> Result<()> {
+    info!("starting maintenance daemon");
+
+    let db = Db::open().await.context("failed to open state database")?;
+
+    let (resource, connection) = connection::new_session_sync()?;
+    let _handle = tokio::spawn(async {
+        let err = resource.await;
+        error!("lost connection to D-Bus: {err:?}");
+    });
+
+    let notifiers = config::load_notifiers(&connection)
+        .await
+        .context("failed to load notifier backends")?;
+
+    let bus = EventBus::new();
+    let _dispatcher = tokio::spawn({
+        let bus = bus.clone();
+        let db = db.clone();
+        async move { bus.run_dispatcher(notifiers, db).await }
+    });
+
+    let mut allowlist = load_journalctl_allowlist().await.unwrap_or_default();
+
+    let ctx = CheckContext { db: &db };
+    let systemctl_check = SystemctlCheck;
+    let updates_check = UpdatesCheck;
+
+    let mut systemctl_ticker = time::interval(Duration::from_secs(systemctl_period));
+    let mut updates_ticker = time::interval(Duration::from_secs(updates_period));
+
+    let mut journal_lines = spawn_journal_follow().await?;
+
+    loop {
+        tokio::select! {
+            _ = systemctl_ticker.tick() => {
+                if let Err(e) = run_check(&systemctl_check, &ctx, &bus).await {
+                    error!("systemctl check failed: {e:?}");
+                }
+            }
+            _ = updates_ticker.tick() => {
+                if let Err(e) = run_check(&updates_check, &ctx, &bus).await {
+                    error!("updates check failed: {e:?}");
+                }
+            }
+            line = journal_lines.next_line() => {
+                match line {
+                    Ok(Some(line)) => {
+                        if let Err(e) = handle_journal_line(&bus, &db, &allowlist, &line).await {
+                            error!("failed to handle journal line: {e:?}");
+                        }
+                    }
+                    Ok(None) => {
+                        info!("journalctl --follow exited; restarting it");
+                        journal_lines = spawn_journal_follow().await?;
+                        allowlist = load_journalctl_allowlist().await.unwrap_or_default();
+                    }
+                    Err(e) => error!("failed to read a line from journalctl: {e:?}"),
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                info!("received shutdown signal");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_check(check: &dyn Check, ctx: &CheckContext<'_>, bus: &EventBus) -> Result<()> {
+    for finding in check.run(ctx).await? {
+        dispatch_finding(bus, finding).await?;
+    }
+
+    Ok(())
+}
+
+async fn spawn_journal_follow() -> Result<Lines<BufReader<ChildStdout>>> {
+    let mut child = Command::new("/usr/bin/journalctl")
+        .args(["--boot", "--priority=err", "--output=json", "--follow"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("failed to spawn journalctl --follow")?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .context("journalctl --follow child had no stdout")?;
+
+    // Not awaited here: the child keeps streaming in the background, and
+    // this task reaps it as soon as it exits.
+    tokio::spawn(async move {
+        let _ = child.wait().await;
+    });
+
+    Ok(BufReader::new(stdout).lines())
+}
+
+async fn handle_journal_line(
+    bus: &EventBus,
+    db: &Db,
+    allowlist: &HashMap<String, JournalctlAllow>,
+    line: &str,
+) -> Result<()> {
+    let entry = serde_json::from_str::<JournalctlEntry>(line)
+        .context("journalctl --follow produced invalid JSON")?;
+
+    if let Some(message) = &entry.message
+        && let Some(allow) = allowlist.get(&entry.identifier)
+        && allow.is_match(message)
+    {
+        return Ok(());
+    }
+
+    let message = entry.message.as_deref().unwrap_or("");
+    if !db.observe_journalctl_error(&entry.identifier, message)? {
+        return Ok(());
+    }
+
+    let entry_for_notified = (entry.identifier.clone(), message.to_string());
+
+    dispatch_finding(
+        bus,
+        Finding {
+            summary: "Unrecognized error in journalctl".to_string(),
+            body: format!("{}: {}", entry.identifier, message),
+            urgency: Urgency::Critical,
+            action_label: "View Errors",
+            action_command: Some(("/usr/bin/xdg-open", vec!["journalctl_new.log".to_string()])),
+            icon: JournalCheck.icon(),
+            mark_notified: Some(Box::new(move |db| {
+                db.mark_journalctl_errors_notified(std::slice::from_ref(&entry_for_notified))
+            })),
+        },
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context as _, Result};
+use rusqlite::{Connection, OptionalExtension as _, params};
+use twox_hash::xxh3;
+
+/// Wraps the `~/.local/state/maintenance/state.db` connection used to
+/// deduplicate notifications across runs.
+///
+/// Cheap to clone: the connection is shared behind a mutex so the event
+/// dispatcher can hold its own handle and mark rows notified once
+/// delivery is actually confirmed, without taking the database away from
+/// the checks that produced them.
+#[derive(Clone)]
+pub struct Db {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl Db {
+    /// Opens (creating if necessary) the state database and runs any
+    /// pending schema migrations.
+    pub async fn open() -> Result<Self> {
+        let home = std::env::var_os("HOME").context("missing HOME environment variable")?;
+
+        let mut path = PathBuf::from(&home);
+        path.extend([".local", "state", "maintenance"]);
+        std::fs::create_dir_all(&path).context("failed to create maintenance state directory")?;
+        path.push("state.db");
+
+        let connection =
+            Connection::open(&path).with_context(|| format!("failed to open {}", path.display()))?;
+
+        let db = Self {
+            connection: Arc::new(Mutex::new(connection)),
+        };
+        db.migrate().context("failed to migrate state database")?;
+
+        Ok(db)
+    }
+
+    fn migrate(&self) -> Result<()> {
+        self.connection.lock().unwrap().execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS journalctl_errors (
+                hash INTEGER PRIMARY KEY,
+                identifier TEXT NOT NULL,
+                message TEXT NOT NULL,
+                first_seen INTEGER NOT NULL,
+                last_seen INTEGER NOT NULL,
+                notified INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS failed_units (
+                unit TEXT PRIMARY KEY,
+                first_seen INTEGER NOT NULL,
+                last_seen INTEGER NOT NULL,
+                notified INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS pending_updates (
+                package TEXT PRIMARY KEY,
+                first_seen INTEGER NOT NULL,
+                last_seen INTEGER NOT NULL,
+                notified INTEGER NOT NULL DEFAULT 0
+            );
+            ",
+        )?;
+
+        Ok(())
+    }
+
+    /// Records a journalctl error keyed on a stable hash of its
+    /// `(SYSLOG_IDENTIFIER, MESSAGE)` pair, returning `true` if it still
+    /// needs to be notified about (either it's new, or a past attempt to
+    /// notify about it never actually got delivered).
+    pub fn observe_journalctl_error(&self, identifier: &str, message: &str) -> Result<bool> {
+        let hash = journalctl_error_hash(identifier, message);
+
+        let now = now_unix()?;
+        let connection = self.connection.lock().unwrap();
+
+        let already_notified = connection
+            .query_row(
+                "SELECT notified FROM journalctl_errors WHERE hash = ?1",
+                params![hash],
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()?;
+
+        match already_notified {
+            Some(notified) => {
+                connection.execute(
+                    "UPDATE journalctl_errors SET last_seen = ?2 WHERE hash = ?1",
+                    params![hash, now],
+                )?;
+                Ok(!notified)
+            }
+            None => {
+                connection.execute(
+                    "INSERT INTO journalctl_errors (hash, identifier, message, first_seen, last_seen, notified)
+                     VALUES (?1, ?2, ?3, ?4, ?4, 0)",
+                    params![hash, identifier, message, now],
+                )?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Marks journalctl errors as notified now that delivery of a
+    /// notification covering them has actually been confirmed.
+    pub fn mark_journalctl_errors_notified(&self, entries: &[(String, String)]) -> Result<()> {
+        let connection = self.connection.lock().unwrap();
+
+        for (identifier, message) in entries {
+            let hash = journalctl_error_hash(identifier, message);
+
+            connection.execute(
+                "UPDATE journalctl_errors SET notified = 1 WHERE hash = ?1",
+                params![hash],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Records a failed systemd unit, returning `true` if it still needs
+    /// to be notified about (either it's new, or a past attempt to notify
+    /// about it never actually got delivered).
+    pub fn observe_failed_unit(&self, unit: &str) -> Result<bool> {
+        let now = now_unix()?;
+        let connection = self.connection.lock().unwrap();
+
+        let already_notified = connection
+            .query_row(
+                "SELECT notified FROM failed_units WHERE unit = ?1",
+                params![unit],
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()?;
+
+        match already_notified {
+            Some(notified) => {
+                connection.execute(
+                    "UPDATE failed_units SET last_seen = ?2 WHERE unit = ?1",
+                    params![unit, now],
+                )?;
+                Ok(!notified)
+            }
+            None => {
+                connection.execute(
+                    "INSERT INTO failed_units (unit, first_seen, last_seen, notified)
+                     VALUES (?1, ?2, ?2, 0)",
+                    params![unit, now],
+                )?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Marks failed units as notified now that delivery of a notification
+    /// covering them has actually been confirmed.
+    pub fn mark_failed_units_notified(&self, units: &[String]) -> Result<()> {
+        if units.is_empty() {
+            return Ok(());
+        }
+
+        let placeholders = units.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        let sql = format!("UPDATE failed_units SET notified = 1 WHERE unit IN ({placeholders})");
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(&sql, rusqlite::params_from_iter(units))?;
+
+        Ok(())
+    }
+
+    /// Records a pending package update, returning `true` if it still
+    /// needs to be notified about (either it's new, or a past attempt to
+    /// notify about it never actually got delivered).
+    pub fn observe_pending_update(&self, package: &str) -> Result<bool> {
+        let now = now_unix()?;
+        let connection = self.connection.lock().unwrap();
+
+        let already_notified = connection
+            .query_row(
+                "SELECT notified FROM pending_updates WHERE package = ?1",
+                params![package],
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()?;
+
+        match already_notified {
+            Some(notified) => {
+                connection.execute(
+                    "UPDATE pending_updates SET last_seen = ?2 WHERE package = ?1",
+                    params![package, now],
+                )?;
+                Ok(!notified)
+            }
+            None => {
+                connection.execute(
+                    "INSERT INTO pending_updates (package, first_seen, last_seen, notified)
+                     VALUES (?1, ?2, ?2, 0)",
+                    params![package, now],
+                )?;
+                Ok(true)
+            }
+        }
+    }
+
+    /// Marks pending updates as notified now that delivery of a
+    /// notification covering them has actually been confirmed.
+    pub fn mark_pending_updates_notified(&self, packages: &[String]) -> Result<()> {
+        if packages.is_empty() {
+            return Ok(());
+        }
+
+        let placeholders = packages.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        let sql =
+            format!("UPDATE pending_updates SET notified = 1 WHERE package IN ({placeholders})");
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(&sql, rusqlite::params_from_iter(packages))?;
+
+        Ok(())
+    }
+
+    /// Clears the failed-unit rows that are no longer present, so a unit
+    /// that recovers and fails again in the future re-notifies.
+    pub fn forget_failed_units_except(&self, units: &[String]) -> Result<()> {
+        let placeholders = units.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        let sql = if units.is_empty() {
+            "DELETE FROM failed_units".to_string()
+        } else {
+            format!("DELETE FROM failed_units WHERE unit NOT IN ({placeholders})")
+        };
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(&sql, rusqlite::params_from_iter(units))?;
+        Ok(())
+    }
+
+    /// Clears the pending-update rows that are no longer present, so a
+    /// package that's updated and becomes outdated again re-notifies.
+    pub fn forget_pending_updates_except(&self, packages: &[String]) -> Result<()> {
+        let placeholders = packages.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        let sql = if packages.is_empty() {
+            "DELETE FROM pending_updates".to_string()
+        } else {
+            format!("DELETE FROM pending_updates WHERE package NOT IN ({placeholders})")
+        };
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(&sql, rusqlite::params_from_iter(packages))?;
+        Ok(())
+    }
+}
+
+/// Hashes a `(SYSLOG_IDENTIFIER, MESSAGE)` pair with a fixed algorithm
+/// (XXH3) so rows in `state.db` survive a Rust toolchain bump. Unlike
+/// `std::collections::hash_map::DefaultHasher`, whose docs explicitly say
+/// its algorithm "should not be relied upon over releases", XXH3's
+/// output is stable for a given input regardless of the compiler used.
+fn journalctl_error_hash(identifier: &str, message: &str) -> i64 {
+    let mut buf = Vec::with_capacity(identifier.len() + message.len() + 1);
+    buf.extend_from_slice(identifier.as_bytes());
+    buf.push(0);
+    buf.extend_from_slice(message.as_bytes());
+    xxh3::hash64(&buf) as i64
+}
+
+fn now_unix() -> Result<i64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("system clock is before the Unix epoch")?
+        .as_secs() as i64)
+}
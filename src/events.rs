@@ -0,0 +1,173 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use log::error;
+use tokio::sync::{Mutex, broadcast, oneshot};
+
+use crate::{
+    NotifyOutcome,
+    check::Finding,
+    db::Db,
+    fan_out_notify,
+    notifier::{InvokedAction, Notifier, Urgency},
+};
+
+/// How long the dispatcher waits after the first event in a batch for
+/// more to arrive before sending a single coalesced notification.
+const COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// A finding published onto the bus, together with a way for the
+/// dispatcher to hand back whatever action the user invoked on the
+/// notification it ends up in.
+pub(crate) struct MaintenanceEvent {
+    pub(crate) finding: Finding,
+    mark_notified: Mutex<Option<Box<dyn FnOnce(&Db) -> Result<()> + Send>>>,
+    responder: Mutex<Option<oneshot::Sender<Option<InvokedAction>>>>,
+}
+
+/// An internal pub/sub bus that decouples checks (producers) from the
+/// single task that owns the notifier backends (the consumer).
+#[derive(Clone)]
+pub(crate) struct EventBus {
+    sender: broadcast::Sender<Arc<MaintenanceEvent>>,
+}
+
+impl EventBus {
+    pub(crate) fn new() -> Self {
+        let (sender, _) = broadcast::channel(32);
+        Self { sender }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Arc<MaintenanceEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Publishes a finding and waits for the dispatcher to report back
+    /// whether the user invoked its action.
+    ///
+    /// Returns a future rather than requiring the caller to block on it
+    /// immediately: to let the coalescing window below actually see more
+    /// than one event, a caller publishing several findings from one run
+    /// should fire them all first and only then await their responses
+    /// (see `main.rs::run_checks`).
+    pub(crate) async fn publish(&self, mut finding: Finding) -> Option<InvokedAction> {
+        let mark_notified = finding.mark_notified.take();
+        let (responder, response) = oneshot::channel();
+        let event = Arc::new(MaintenanceEvent {
+            finding,
+            mark_notified: Mutex::new(mark_notified),
+            responder: Mutex::new(Some(responder)),
+        });
+
+        // No subscriber means there's nowhere for the notification to go;
+        // the check still ran, it just has nothing to report to.
+        let _ = self.sender.send(event);
+
+        response.await.ok().flatten()
+    }
+
+    /// Runs the single dispatcher task that owns the notifier backends:
+    /// it decides urgency, coalesces events that land close together
+    /// into one notification, persists dedup state as notified once
+    /// delivery is confirmed, and routes the invoked action back to
+    /// each event's publisher.
+    pub(crate) async fn run_dispatcher(&self, notifiers: Vec<Box<dyn Notifier>>, db: Db) {
+        let mut receiver = self.subscribe();
+
+        loop {
+            let first = match receiver.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Closed) => break,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    error!("event dispatcher lagged behind by {skipped} events");
+                    continue;
+                }
+            };
+
+            let mut batch = vec![first];
+
+            let coalesce_deadline = tokio::time::sleep(COALESCE_WINDOW);
+            tokio::pin!(coalesce_deadline);
+            loop {
+                tokio::select! {
+                    () = &mut coalesce_deadline => break,
+                    recv = receiver.recv() => match recv {
+                        Ok(event) => batch.push(event),
+                        Err(_) => break,
+                    },
+                }
+            }
+
+            // A coalesced notification can only carry one action, and
+            // its batch can hold unrelated findings (e.g. a failed unit
+            // and an available update) whose `action_command`s shouldn't
+            // be conflated, so a batch of more than one event never
+            // offers an action at all: no "default" button is sent to
+            // the notifier, and every responder in the batch gets `None`
+            // regardless of what the backend reports back.
+            let is_coalesced = batch.len() > 1;
+
+            let outcome = notify_batch(&notifiers, &batch).await;
+
+            if outcome.delivered {
+                for event in &batch {
+                    if let Some(mark_notified) = event.mark_notified.lock().await.take()
+                        && let Err(e) = mark_notified(&db)
+                    {
+                        error!("failed to persist notified state: {e:?}");
+                    }
+                }
+            }
+
+            let invoked = if is_coalesced { None } else { outcome.invoked };
+
+            for event in batch {
+                if let Some(responder) = event.responder.lock().await.take() {
+                    let _ = responder.send(invoked.clone());
+                }
+            }
+        }
+    }
+}
+
+async fn notify_batch(
+    notifiers: &[Box<dyn Notifier>],
+    batch: &[Arc<MaintenanceEvent>],
+) -> NotifyOutcome {
+    let urgency = if batch
+        .iter()
+        .any(|event| matches!(event.finding.urgency, Urgency::Critical))
+    {
+        Urgency::Critical
+    } else {
+        Urgency::Normal
+    };
+
+    let (summary, body, actions, icon) = if let [event] = batch {
+        (
+            event.finding.summary.clone(),
+            event.finding.body.clone(),
+            vec!["default", event.finding.action_label],
+            event.finding.icon,
+        )
+    } else {
+        let summary = format!("{} maintenance notifications", batch.len());
+        let body = batch
+            .iter()
+            .map(|event| format!("{}: {}", event.finding.summary, event.finding.body))
+            .collect::<Vec<_>>()
+            .join("\n");
+        // No actions: see the comment on `is_coalesced` in `run_dispatcher`.
+        (summary, body, Vec::new(), batch[0].finding.icon)
+    };
+
+    fan_out_notify(notifiers, &summary, &body, urgency, &actions, icon)
+        .await
+        .unwrap_or_else(|e| {
+            error!("failed to deliver coalesced notification: {e:?}");
+            NotifyOutcome {
+                delivered: false,
+                invoked: None,
+            }
+        })
+}
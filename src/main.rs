@@ -1,35 +1,56 @@
+mod allowlist;
+mod check;
+mod checks;
+mod cli;
+mod config;
+mod daemon;
+mod db;
+mod events;
 mod interfaces;
 mod notifications;
-
-use core::fmt::{self, Write as _};
-use std::{collections::HashMap, env, path::PathBuf};
+mod notifier;
 
 use anyhow::{Context as _, Result};
-use dbus::arg::Variant;
+use clap::Parser as _;
 use dbus_tokio::connection;
 use log::{LevelFilter, error, info};
-use regex::RegexSet;
-use serde::{Deserialize, Deserializer, de};
 use systemd_journal_logger::JournalLog;
-use tokio::{fs, process::Command};
+use tokio::process::Command;
 
-use crate::notifications::{Notification, Notifications};
+use crate::{
+    check::{Check, CheckContext, Checks, Finding},
+    cli::{Cli, CheckKind, Command as CliCommand},
+    db::Db,
+    events::EventBus,
+    notifier::{InvokedAction, Notifier, Urgency},
+};
 
 #[tokio::main]
 async fn main() {
     JournalLog::new().unwrap().install().unwrap();
     log::set_max_level(LevelFilter::Info);
 
-    info!("running maintenance");
+    let cli = Cli::parse();
 
-    if let Err(e) = run_checks().await {
+    let result = match cli.command {
+        CliCommand::Run { check } => run_checks(check).await,
+        CliCommand::Allow { command } => allowlist::dispatch(command).await,
+        CliCommand::Daemon {
+            systemctl_period,
+            updates_period,
+        } => daemon::run(systemctl_period, updates_period).await,
+    };
+
+    if let Err(e) = result {
         error!("encountered an internal error: {e:?}");
     }
-
-    info!("finished");
 }
 
-async fn run_checks() -> Result<()> {
+async fn run_checks(check: Option<CheckKind>) -> Result<()> {
+    info!("running maintenance");
+
+    let db = Db::open().await.context("failed to open state database")?;
+
     let (resource, connection) = connection::new_session_sync()?;
 
     let _handle = tokio::spawn(async {
@@ -37,283 +58,122 @@ async fn run_checks() -> Result<()> {
         error!("lost connection to D-Bus: {err:?}");
     });
 
-    let notifications = Notifications::start(connection.clone())
-        .await
-        .context("failed to start notifications")?;
-
-    check_systemctl_failures(notifications.clone())
-        .await
-        .context("failed to check systemctl failures")?;
-    check_journalctl_errors(notifications.clone())
-        .await
-        .context("failed to check journalctl errors")?;
-    check_updates(notifications.clone())
-        .await
-        .context("failed to check for updates")?;
-
-    notifications
-        .stop()
+    let notifiers = config::load_notifiers(&connection)
         .await
-        .context("failed to stop notifications")?;
+        .context("failed to load notifier backends")?;
 
-    Ok(())
-}
-
-#[derive(Deserialize)]
-struct SystemctlUnit {
-    unit: String,
-    description: String,
-}
+    let bus = EventBus::new();
+    let dispatcher = tokio::spawn({
+        let bus = bus.clone();
+        let db = db.clone();
+        async move { bus.run_dispatcher(notifiers, db).await }
+    });
 
-async fn check_systemctl_failures(notifications: Notifications) -> Result<()> {
-    info!("checking for systemctl failures");
+    let ctx = CheckContext { db: &db };
 
-    let output = Command::new("/usr/bin/systemctl")
-        .args(["--failed", "--output=json"])
-        .output()
-        .await
-        .context("failed to run systemctl")?
-        .stdout;
-    let failed = serde_json::from_slice::<Vec<SystemctlUnit>>(&output)
-        .context("failed to parse systemctl output as json")?;
+    let mut findings = Vec::new();
+    for check_impl in Checks::all().iter() {
+        if let Some(check) = check
+            && check.as_name() != check_impl.name()
+        {
+            continue;
+        }
 
-    if failed.is_empty() {
-        return Ok(());
-    }
+        info!("running check '{}' ({})", check_impl.name(), check_impl.icon());
 
-    let summary;
-    let body;
+        let check_findings = check_impl
+            .run(&ctx)
+            .await
+            .with_context(|| format!("failed to run the '{}' check", check_impl.name()))?;
 
-    if failed.len() == 1 {
-        summary = "Systemd unit failed to load";
-        body = format!(
-            "'{}' ({}) failed to start normally.",
-            failed[0].description, failed[0].unit
+        findings.extend(
+            check_findings
+                .into_iter()
+                .map(|finding| (check_impl.name(), finding)),
         );
-    } else {
-        summary = "Multiple systemd units failed to load";
-        body = format!("{} units failed to start normally.", failed.len());
     }
 
-    let mut hints = HashMap::new();
-    hints.insert("urgency".to_string(), Variant(Box::new(2u8) as _));
+    // Dispatched concurrently rather than one at a time: publishing
+    // every finding from this run up front, instead of awaiting each
+    // one's response before the next, is what lets the dispatcher's
+    // coalescing window in `events.rs` actually see more than one event.
+    let dispatches = findings.into_iter().map(|(name, finding)| {
+        let bus = bus.clone();
+        async move {
+            dispatch_finding(&bus, finding)
+                .await
+                .with_context(|| format!("failed to dispatch a finding from the '{name}' check"))
+        }
+    });
 
-    let response = notifications
-        .notify(
-            "Maintenance",
-            0,
-            "dialog-warning-symbolid",
-            summary,
-            &body,
-            vec!["default", "Investigate"],
-            hints,
-            -1,
-        )
-        .await?;
+    for result in futures::future::join_all(dispatches).await {
+        result?;
+    }
 
-    if let Notification::ActionInvoked(action_invoked) = response {
-        assert_eq!(action_invoked.arg_1, "default");
+    dispatcher.abort();
 
-        Command::new("/usr/bin/kgx")
-            .arg("--command=systemctl --failed")
-            .output()
-            .await
-            .context("failed to spawn systemctl investigation terminal")?;
-    }
+    info!("finished");
 
     Ok(())
 }
 
-async fn check_updates(notifications: Notifications) -> Result<()> {
-    info!("checking for package updates");
-
-    let output = Command::new("/usr/bin/checkupdates")
-        .output()
-        .await
-        .context("failed to run checkupdates")?
-        .stdout;
-    let updates = str::from_utf8(&output)
-        .context("checkupdates output was not UTF-8")?
-        .trim_end();
-
-    if updates.is_empty() {
-        return Ok(());
-    }
-
-    let count = updates.lines().count();
-    let summary = "Updates available";
-    let body = if count == 1 {
-        let package = updates.split_once(' ').unwrap_or((updates, "")).0;
-        format!("'{package}' is ready to update.")
-    } else {
-        format!("{count} packages are ready to update.")
-    };
+/// Publishes a finding to the event bus and runs its action command if
+/// the dispatcher reports the user invoked it.
+pub(crate) async fn dispatch_finding(bus: &EventBus, finding: Finding) -> Result<()> {
+    let action_command = finding.action_command.clone();
 
-    let mut hints = HashMap::new();
-    hints.insert("urgency".to_string(), Variant(Box::new(2u8) as _));
+    let invoked = bus.publish(finding).await;
 
-    let response = notifications
-        .notify(
-            "Maintenance",
-            0,
-            "software-update-available",
-            summary,
-            &body,
-            vec!["default", "Update"],
-            hints,
-            -1,
-        )
-        .await?;
+    if let Some(invoked) = invoked {
+        assert_eq!(invoked.action, "default");
 
-    if let Notification::ActionInvoked(action_invoked) = response {
-        assert_eq!(action_invoked.arg_1, "default");
-
-        Command::new("/usr/bin/kgx")
-            .arg("--command=sudo pacman -Syu")
-            .output()
-            .await
-            .context("failed to spawn upgrade terminal")?;
+        if let Some((program, args)) = action_command {
+            Command::new(program)
+                .args(args)
+                .output()
+                .await
+                .context("failed to spawn finding's action command")?;
+        }
     }
 
     Ok(())
 }
 
-struct JournalctlAllow {
-    matcher: RegexSet,
+/// The result of fanning a notification out to every configured backend.
+pub(crate) struct NotifyOutcome {
+    /// Whether at least one backend accepted the notification. Dedup
+    /// state should only be marked notified once this is `true` —
+    /// otherwise a finding no backend could deliver (e.g. no notifiers
+    /// configured, or every backend erroring) would be marked seen
+    /// without the user ever seeing it.
+    pub(crate) delivered: bool,
+    pub(crate) invoked: Option<InvokedAction>,
 }
 
-impl<'de> Deserialize<'de> for JournalctlAllow {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct BuildMatcher;
-
-        impl<'de> de::Visitor<'de> for BuildMatcher {
-            type Value = RegexSet;
-
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
-            where
-                A: de::SeqAccess<'de>,
-            {
-                let mut matchers = Vec::new();
-
-                while let Some(regex) = seq.next_element::<String>()? {
-                    matchers.push(format!("^{regex}$"));
-                }
-
-                RegexSet::new(matchers.iter())
-                    .map_err(|_| de::Error::custom("failed to build regex matchers"))
-            }
-
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("a set of regexes")
+/// Delivers a notification to every configured backend, returning
+/// whether delivery succeeded and the action invoked on whichever
+/// backend (if any) is capable of carrying interactive actions.
+pub(crate) async fn fan_out_notify(
+    notifiers: &[Box<dyn Notifier>],
+    summary: &str,
+    body: &str,
+    urgency: Urgency,
+    actions: &[&str],
+    icon: &str,
+) -> Result<NotifyOutcome> {
+    let mut delivered = false;
+    let mut invoked = None;
+
+    for notifier in notifiers {
+        match notifier.notify(summary, body, urgency, actions, icon).await {
+            Ok(Some(action)) => {
+                delivered = true;
+                invoked = Some(action);
             }
+            Ok(None) => delivered = true,
+            Err(e) => error!("failed to deliver notification: {e:?}"),
         }
-
-        Ok(Self {
-            matcher: deserializer.deserialize_any(BuildMatcher)?,
-        })
     }
-}
-
-#[derive(Deserialize)]
-struct JournalctlEntry {
-    #[serde(rename(deserialize = "SYSLOG_IDENTIFIER"))]
-    identifier: String,
-    #[serde(rename(deserialize = "MESSAGE"))]
-    message: Option<String>,
-}
-
-async fn check_journalctl_errors(notifications: Notifications) -> Result<()> {
-    info!("checking for journalctl errors from boot");
-
-    let home = env::var_os("HOME").context("missing HOME environment variable")?;
-
-    let mut allowlist_path = PathBuf::from(&home);
-    allowlist_path.extend([".local", "state", "maintenance", "journalctl_allow.json"]);
-
-    let allowlist = fs::read_to_string(&allowlist_path)
-        .await
-        .context("failed to read allowlist from journalctl_allow.json")?;
-    let allowlist = serde_json::from_str::<HashMap<String, JournalctlAllow>>(&allowlist)
-        .context("failed to deserialize allowlist from journalctl_allow.json")?;
-
-    let output = Command::new("/usr/bin/journalctl")
-        .args(["--boot", "--priority=err", "--output=json"])
-        .output()
-        .await
-        .context("failed to run journalctl")?
-        .stdout;
-    let errors = serde_json::Deserializer::from_str(
-        str::from_utf8(&output).context("journalctl output was invalid UTF-8")?,
-    )
-    .into_iter::<JournalctlEntry>();
 
-    let mut error_log_contents = String::new();
-    let mut unmatched_count = 0;
-    for error in errors {
-        let error = error.context("journalctl produced invalid JSON")?;
-        if let Some(message) = &error.message
-            && let Some(allow) = allowlist.get(&error.identifier)
-            && allow.matcher.is_match(message)
-        {
-            continue;
-        }
-
-        unmatched_count += 1;
-
-        writeln!(
-            &mut error_log_contents,
-            "{}: {}",
-            error.identifier,
-            error.message.as_deref().unwrap_or("")
-        )?;
-    }
-
-    let mut error_log_path = PathBuf::from(&home);
-    error_log_path.extend([".local", "state", "maintenance", "journalctl_new.log"]);
-
-    fs::write(error_log_path, error_log_contents)
-        .await
-        .context("failed to create journalctl log")?;
-
-    if unmatched_count == 0 {
-        return Ok(());
-    }
-
-    let summary = "Unrecognized errors in journalctl";
-    let body = if unmatched_count == 1 {
-        "1 error not found in allowlist.".to_string()
-    } else {
-        format!("{unmatched_count} errors not found in allowlist.")
-    };
-
-    let mut hints = HashMap::new();
-    hints.insert("urgency".to_string(), Variant(Box::new(2u8) as _));
-
-    let response = notifications
-        .notify(
-            "Maintenance",
-            0,
-            "dialog-warning-symbolic",
-            summary,
-            &body,
-            vec!["default", "View Errors"],
-            hints,
-            -1,
-        )
-        .await?;
-
-    if let Notification::ActionInvoked(action_invoked) = response {
-        assert_eq!(action_invoked.arg_1, "default");
-
-        Command::new("/usr/bin/xdg-open")
-            .arg("journalctl_new.log")
-            .output()
-            .await
-            .context("failed to open journalctl log")?;
-    }
-
-    Ok(())
+    Ok(NotifyOutcome { delivered, invoked })
 }
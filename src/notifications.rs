@@ -1,12 +1,16 @@
 use std::{collections::HashMap, sync::Arc};
 
 use anyhow::{Context as _, Result};
+use async_trait::async_trait;
 use futures::{StreamExt as _, channel::oneshot};
 use log::{error, info};
 use tokio::sync::Mutex;
 use zbus::{AsyncDrop as _, Connection, fdo::DBusProxy, names::BusName, zvariant::Value};
 
-use crate::interfaces::{ActionInvoked, NotificationClosed, NotificationsProxy};
+use crate::{
+    interfaces::{ActionInvoked, NotificationClosed, NotificationsProxy},
+    notifier::{self, Notifier},
+};
 
 pub enum Notification {
     Closed(#[expect(unused)] NotificationClosed),
@@ -154,12 +158,20 @@ impl Notifications {
         summary: &str,
         body: &str,
         actions: &[&str],
+        urgency: notifier::Urgency,
         expire_timeout: i32,
     ) -> Result<Notification> {
         let mut state_guard = self.state.lock().await;
 
+        let urgency_byte: u8 = match urgency {
+            notifier::Urgency::Low => 0,
+            notifier::Urgency::Normal => 1,
+            notifier::Urgency::Critical => 2,
+        };
+
         let mut hints = HashMap::new();
         hints.insert("resident", &Value::Bool(true));
+        hints.insert("urgency", &Value::U8(urgency_byte));
 
         let id = self
             .notifications
@@ -191,3 +203,27 @@ impl Notifications {
         Ok(response)
     }
 }
+
+#[async_trait]
+impl Notifier for Notifications {
+    async fn notify(
+        &self,
+        summary: &str,
+        body: &str,
+        urgency: notifier::Urgency,
+        actions: &[&str],
+        icon: &str,
+    ) -> Result<Option<notifier::InvokedAction>> {
+        let response = Notifications::notify(
+            self, "Maintenance", 0, icon, summary, body, actions, urgency, -1,
+        )
+        .await?;
+
+        Ok(match response {
+            Notification::ActionInvoked(action_invoked) => Some(notifier::InvokedAction {
+                action: action_invoked.arg_1.clone(),
+            }),
+            Notification::Closed(_) => None,
+        })
+    }
+}
@@ -0,0 +1,36 @@
+pub mod matrix;
+pub mod smtp;
+pub mod webhook;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Urgency level for a notification, mirroring the D-Bus notification spec.
+#[derive(Clone, Copy, Debug)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+/// An action the user invoked on a notification that supports them.
+#[derive(Clone, Debug)]
+pub struct InvokedAction {
+    pub action: String,
+}
+
+/// A destination a notification can be delivered to.
+///
+/// Only backends that can carry interactive actions (currently just
+/// D-Bus) will ever resolve to `Some`; the rest are fire-and-forget.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(
+        &self,
+        summary: &str,
+        body: &str,
+        urgency: Urgency,
+        actions: &[&str],
+        icon: &str,
+    ) -> Result<Option<InvokedAction>>;
+}
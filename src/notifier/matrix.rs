@@ -0,0 +1,83 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::notifier::{InvokedAction, Notifier, Urgency};
+
+/// Delivers notifications as messages in a Matrix room via the
+/// `PUT /send/m.room.message/{txnId}` REST endpoint.
+pub struct Matrix {
+    client: reqwest::Client,
+    homeserver: String,
+    access_token: String,
+    room_id: String,
+    txn_counter: AtomicU64,
+}
+
+impl Matrix {
+    pub fn new(homeserver: &str, access_token: &str, room_id: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            homeserver: homeserver.trim_end_matches('/').to_string(),
+            access_token: access_token.to_string(),
+            room_id: room_id.to_string(),
+            txn_counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Builds a transaction id that's unique for this `Matrix` instance,
+    /// as the Client-Server API requires for `PUT .../send/{eventType}/{txnId}`.
+    fn next_txn_id(&self) -> String {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let counter = self.txn_counter.fetch_add(1, Ordering::Relaxed);
+        format!("maintenance-{millis}-{counter}")
+    }
+}
+
+#[derive(Serialize)]
+struct RoomMessage<'a> {
+    msgtype: &'static str,
+    body: &'a str,
+}
+
+#[async_trait]
+impl Notifier for Matrix {
+    async fn notify(
+        &self,
+        summary: &str,
+        body: &str,
+        _urgency: Urgency,
+        _actions: &[&str],
+        _icon: &str,
+    ) -> Result<Option<InvokedAction>> {
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
+            self.homeserver,
+            self.room_id,
+            self.next_txn_id()
+        );
+
+        self.client
+            .put(url)
+            .bearer_auth(&self.access_token)
+            .json(&RoomMessage {
+                msgtype: "m.text",
+                body: &format!("{summary}\n{body}"),
+            })
+            .send()
+            .await
+            .context("failed to send Matrix notification")?
+            .error_for_status()
+            .context("Matrix homeserver returned an error status")?;
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport as _, Message, Tokio1Executor,
+    message::header::ContentType,
+    transport::smtp::authentication::Credentials,
+};
+
+use crate::notifier::{InvokedAction, Notifier, Urgency};
+
+/// Delivers notifications by sending an email through an SMTP relay.
+pub struct Smtp {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: String,
+    to: String,
+}
+
+impl Smtp {
+    pub fn new(host: &str, port: u16, username: &str, password: &str, from: &str, to: &str) -> Result<Self> {
+        // `relay` configures implicit TLS (SMTPS), which only works on
+        // port 465; `.port()` alone doesn't change the TLS mode, so a
+        // relay on the common STARTTLS submission port (587) needs the
+        // STARTTLS-flavored builder instead.
+        let builder = if port == 465 {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+        }
+        .context("failed to configure SMTP relay")?;
+
+        let transport = builder
+            .port(port)
+            .credentials(Credentials::new(username.to_string(), password.to_string()))
+            .build();
+
+        Ok(Self {
+            transport,
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for Smtp {
+    async fn notify(
+        &self,
+        summary: &str,
+        body: &str,
+        urgency: Urgency,
+        _actions: &[&str],
+        _icon: &str,
+    ) -> Result<Option<InvokedAction>> {
+        let prefix = match urgency {
+            Urgency::Low => "",
+            Urgency::Normal => "",
+            Urgency::Critical => "[urgent] ",
+        };
+
+        let message = Message::builder()
+            .from(self.from.parse().context("invalid SMTP from address")?)
+            .to(self.to.parse().context("invalid SMTP to address")?)
+            .subject(format!("{prefix}{summary}"))
+            .header(ContentType::TEXT_PLAIN)
+            .body(body.to_string())
+            .context("failed to build notification email")?;
+
+        self.transport
+            .send(message)
+            .await
+            .context("failed to send notification email")?;
+
+        Ok(None)
+    }
+}
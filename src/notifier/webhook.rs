@@ -0,0 +1,60 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::notifier::{InvokedAction, Notifier, Urgency};
+
+/// Delivers notifications by POSTing a JSON payload to a generic webhook.
+pub struct Webhook {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl Webhook {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    summary: &'a str,
+    body: &'a str,
+    urgency: &'static str,
+}
+
+#[async_trait]
+impl Notifier for Webhook {
+    async fn notify(
+        &self,
+        summary: &str,
+        body: &str,
+        urgency: Urgency,
+        _actions: &[&str],
+        _icon: &str,
+    ) -> Result<Option<InvokedAction>> {
+        let urgency = match urgency {
+            Urgency::Low => "low",
+            Urgency::Normal => "normal",
+            Urgency::Critical => "critical",
+        };
+
+        self.client
+            .post(&self.url)
+            .json(&Payload {
+                summary,
+                body,
+                urgency,
+            })
+            .send()
+            .await
+            .context("failed to POST webhook notification")?
+            .error_for_status()
+            .context("webhook returned an error status")?;
+
+        Ok(None)
+    }
+}